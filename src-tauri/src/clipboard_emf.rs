@@ -5,7 +5,7 @@
 use std::ptr::null_mut;
 
 #[cfg(windows)]
-use windows::Win32::Foundation::{HWND, RECT};
+use windows::Win32::Foundation::{HGLOBAL, HWND, RECT};
 #[cfg(windows)]
 use windows::Win32::Graphics::Gdi::{
     CloseEnhMetaFile, CreateCompatibleDC, CreateEnhMetaFileW, CreateDIBSection,
@@ -14,30 +14,66 @@ use windows::Win32::Graphics::Gdi::{
 };
 #[cfg(windows)]
 use windows::Win32::System::DataExchange::{
-    CloseClipboard, EmptyClipboard, OpenClipboard, SetClipboardData, CF_ENHMETAFILE, CF_UNICODETEXT,
+    CloseClipboard, EmptyClipboard, EnumClipboardFormats, GetClipboardData, GetClipboardFormatNameW,
+    OpenClipboard, SetClipboardData, CF_DIB, CF_ENHMETAFILE, CF_UNICODETEXT,
 };
 #[cfg(windows)]
-use windows::Win32::System::Memory::{GlobalAlloc, GlobalLock, GlobalUnlock, GMEM_MOVEABLE};
+use windows::Win32::System::Memory::{GlobalAlloc, GlobalLock, GlobalSize, GlobalUnlock, GMEM_MOVEABLE};
 #[cfg(windows)]
 use windows::Win32::UI::WindowsAndMessaging::{GetDesktopWindow, RegisterClipboardFormatW};
 #[cfg(windows)]
 use windows::core::PCWSTR;
 
+/// Richest chemical clipboard formats found on a read (CDX, MOL, or bare EMF/DIB).
+#[derive(Debug, Default, serde::Serialize)]
+pub struct ClipboardContents {
+    pub cdx: Option<Vec<u8>>,
+    pub mol: Option<String>,
+    pub available_formats: Vec<String>,
+}
+
+/// Whether a clipboard write replaces everything already there or appends alongside it.
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
+pub enum ClipboardMode {
+    #[default]
+    Replace,
+    Append,
+}
+
+/// Open the clipboard, retrying with a short, growing backoff to survive lock contention.
+#[cfg(windows)]
+fn open_clipboard_with_retry() -> Result<(), String> {
+    const MAX_ATTEMPTS: u32 = 10;
+    for attempt in 0..MAX_ATTEMPTS {
+        if unsafe { OpenClipboard(HWND::default()).as_bool() } {
+            return Ok(());
+        }
+        std::thread::sleep(std::time::Duration::from_millis(5 * (attempt as u64 + 1)));
+    }
+    Err("OpenClipboard failed".to_string())
+}
+
 /// Write EMF + MOL + CDX to clipboard (ChemDraw-style, Windows only).
 #[cfg(windows)]
 pub fn write_chemdraw_style_to_clipboard(
     png_bytes: &[u8],
     mol_text: &str,
     cdx_bytes: Option<&[u8]>,
+    mode: ClipboardMode,
 ) -> Result<(), String> {
     let h_emf = create_emf_from_png(png_bytes)?;
 
-    unsafe {
-        if !OpenClipboard(HWND::default()).as_bool() {
+    if let Err(e) = open_clipboard_with_retry() {
+        unsafe {
             let _ = DeleteEnhMetaFile(h_emf);
-            return Err("OpenClipboard failed".to_string());
         }
-        let _ = EmptyClipboard();
+        return Err(e);
+    }
+
+    unsafe {
+        if matches!(mode, ClipboardMode::Replace) {
+            let _ = EmptyClipboard();
+        }
 
         // 1. EMF
         let _ = SetClipboardData(CF_ENHMETAFILE, h_emf);
@@ -78,6 +114,93 @@ pub fn write_chemdraw_style_to_clipboard(
     }
 }
 
+/// Read CDX/MOL/EMF/DIB formats currently on the clipboard (Windows only).
+#[cfg(windows)]
+pub fn read_from_clipboard() -> Result<ClipboardContents, String> {
+    let mut contents = ClipboardContents::default();
+
+    open_clipboard_with_retry()?;
+
+    unsafe {
+        let mut format = 0u32;
+        loop {
+            format = EnumClipboardFormats(format);
+            if format == 0 {
+                break;
+            }
+
+            if format == CF_UNICODETEXT.0 as u32 {
+                contents.available_formats.push("CF_UNICODETEXT".to_string());
+                if contents.mol.is_none() {
+                    contents.mol = read_unicode_text(format);
+                }
+            } else if format == CF_ENHMETAFILE.0 as u32 {
+                contents.available_formats.push("CF_ENHMETAFILE".to_string());
+            } else if format == CF_DIB.0 as u32 {
+                contents.available_formats.push("CF_DIB".to_string());
+            } else if let Some(name) = registered_format_name(format) {
+                if name.eq_ignore_ascii_case("CDX") && contents.cdx.is_none() {
+                    contents.cdx = read_global_bytes(format);
+                }
+                contents.available_formats.push(name);
+            }
+        }
+
+        let _ = CloseClipboard();
+    }
+
+    Ok(contents)
+}
+
+/// Resolve the name of a registered clipboard format (e.g. "CDX").
+#[cfg(windows)]
+fn registered_format_name(format: u32) -> Option<String> {
+    let mut buf = [0u16; 256];
+    let len = unsafe {
+        GetClipboardFormatNameW(format, windows::core::PWSTR(buf.as_mut_ptr()), buf.len() as i32)
+    };
+    if len == 0 {
+        return None;
+    }
+    Some(String::from_utf16_lossy(&buf[..len as usize]))
+}
+
+/// Copy the bytes behind a clipboard format; never frees the system-owned handle.
+#[cfg(windows)]
+fn read_global_bytes(format: u32) -> Option<Vec<u8>> {
+    unsafe {
+        let handle = GetClipboardData(format).ok()?;
+        let hglobal = HGLOBAL(handle.0);
+        let size = GlobalSize(hglobal);
+        let ptr = GlobalLock(hglobal);
+        if ptr.is_null() {
+            return None;
+        }
+        let bytes = std::slice::from_raw_parts(ptr as *const u8, size).to_vec();
+        let _ = GlobalUnlock(hglobal);
+        Some(bytes)
+    }
+}
+
+/// Read `CF_UNICODETEXT` as a candidate MOL string, trimmed at the first NUL.
+#[cfg(windows)]
+fn read_unicode_text(format: u32) -> Option<String> {
+    unsafe {
+        let handle = GetClipboardData(format).ok()?;
+        let hglobal = HGLOBAL(handle.0);
+        let size = GlobalSize(hglobal);
+        let ptr = GlobalLock(hglobal) as *const u16;
+        if ptr.is_null() {
+            return None;
+        }
+        let units = std::slice::from_raw_parts(ptr, size / 2);
+        let nul_pos = units.iter().position(|&c| c == 0).unwrap_or(units.len());
+        let text = String::from_utf16_lossy(&units[..nul_pos]);
+        let _ = GlobalUnlock(hglobal);
+        Some(text)
+    }
+}
+
 #[cfg(windows)]
 fn create_emf_from_png(png_bytes: &[u8]) -> Result<windows::Win32::Graphics::Gdi::HENHMETAFILE, String> {
     use image::GenericImageView;
@@ -166,9 +289,50 @@ fn create_emf_from_png(png_bytes: &[u8]) -> Result<windows::Win32::Graphics::Gdi
     }
 }
 
+/// Build the EMF the same way as the clipboard path, then serialize it to a
+/// standalone `.emf` file so the vector image survives outside the live
+/// clipboard session (e.g. for Word/PowerPoint).
+#[cfg(windows)]
+pub fn write_emf_file(path: &str, png_bytes: &[u8]) -> Result<(), String> {
+    use windows::Win32::Graphics::Gdi::GetEnhMetaFileBits;
+
+    let ext = std::path::Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("");
+    if !ext.eq_ignore_ascii_case("emf") {
+        return Err(format!("Invalid file type: {}", ext));
+    }
+    let file_path = crate::long_path(path);
+
+    let h_emf = create_emf_from_png(png_bytes)?;
+
+    let result = (|| unsafe {
+        let size = GetEnhMetaFileBits(h_emf, 0, None);
+        if size == 0 {
+            return Err("GetEnhMetaFileBits failed".to_string());
+        }
+
+        let mut buffer = vec![0u8; size as usize];
+        let written = GetEnhMetaFileBits(h_emf, size, Some(buffer.as_mut_ptr()));
+        if written == 0 {
+            return Err("GetEnhMetaFileBits failed".to_string());
+        }
+        buffer.truncate(written as usize);
+
+        std::fs::write(&file_path, &buffer).map_err(|e| e.to_string())
+    })();
+
+    unsafe {
+        let _ = DeleteEnhMetaFile(h_emf);
+    }
+
+    result
+}
+
 /// Write CDX only to clipboard (Windows).
 #[cfg(windows)]
-pub fn write_cdx_only_to_clipboard(cdx_bytes: &[u8]) -> Result<(), String> {
+pub fn write_cdx_only_to_clipboard(cdx_bytes: &[u8], mode: ClipboardMode) -> Result<(), String> {
     if cdx_bytes.is_empty() {
         return Err("Empty CDX data".to_string());
     }
@@ -177,11 +341,12 @@ pub fn write_cdx_only_to_clipboard(cdx_bytes: &[u8]) -> Result<(), String> {
     if cdx_format == 0 {
         return Err("RegisterClipboardFormat CDX failed".to_string());
     }
+    open_clipboard_with_retry()?;
+
     unsafe {
-        if !OpenClipboard(HWND::default()).as_bool() {
-            return Err("OpenClipboard failed".to_string());
+        if matches!(mode, ClipboardMode::Replace) {
+            let _ = EmptyClipboard();
         }
-        let _ = EmptyClipboard();
         let size = cdx_bytes.len();
         if let Some(h_cdx) = GlobalAlloc(GMEM_MOVEABLE, size).ok() {
             if let Some(ptr) = GlobalLock(h_cdx) {
@@ -197,8 +362,8 @@ pub fn write_cdx_only_to_clipboard(cdx_bytes: &[u8]) -> Result<(), String> {
 
 /// Legacy: EMF only (for backward compat).
 #[cfg(windows)]
-pub fn write_png_as_emf_to_clipboard(png_bytes: &[u8]) -> Result<(), String> {
-    write_chemdraw_style_to_clipboard(png_bytes, "", None)
+pub fn write_png_as_emf_to_clipboard(png_bytes: &[u8], mode: ClipboardMode) -> Result<(), String> {
+    write_chemdraw_style_to_clipboard(png_bytes, "", None, mode)
 }
 
 #[cfg(not(windows))]
@@ -206,16 +371,27 @@ pub fn write_chemdraw_style_to_clipboard(
     _png_bytes: &[u8],
     _mol_text: &str,
     _cdx_bytes: Option<&[u8]>,
+    _mode: ClipboardMode,
 ) -> Result<(), String> {
     Err("ChemDraw-style clipboard is only supported on Windows".to_string())
 }
 
 #[cfg(not(windows))]
-pub fn write_cdx_only_to_clipboard(_cdx_bytes: &[u8]) -> Result<(), String> {
+pub fn write_cdx_only_to_clipboard(_cdx_bytes: &[u8], _mode: ClipboardMode) -> Result<(), String> {
     Err("CDX clipboard is only supported on Windows".to_string())
 }
 
 #[cfg(not(windows))]
-pub fn write_png_as_emf_to_clipboard(_png_bytes: &[u8]) -> Result<(), String> {
+pub fn write_png_as_emf_to_clipboard(_png_bytes: &[u8], _mode: ClipboardMode) -> Result<(), String> {
     Err("EMF clipboard is only supported on Windows".to_string())
 }
+
+#[cfg(not(windows))]
+pub fn read_from_clipboard() -> Result<ClipboardContents, String> {
+    Err("Clipboard read is only supported on Windows".to_string())
+}
+
+#[cfg(not(windows))]
+pub fn write_emf_file(_path: &str, _png_bytes: &[u8]) -> Result<(), String> {
+    Err("EMF export is only supported on Windows".to_string())
+}
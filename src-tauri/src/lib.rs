@@ -4,9 +4,69 @@ mod clipboard_emf;
 
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
+/// Normalize a path for filesystem calls, prefixing it for long-path support on Windows.
+#[cfg(windows)]
+pub(crate) fn long_path(path: &str) -> PathBuf {
+    let raw = Path::new(path);
+    if let Ok(canonical) = raw.canonicalize() {
+        return canonical;
+    }
+
+    // `canonicalize` fails for paths that don't exist yet (e.g. a file
+    // about to be written), so make it absolute, `\`-separated, and free of
+    // `.`/`..` components ourselves -- the `\\?\` prefix is passed to the
+    // kernel verbatim with no component parsing, so any of those left in
+    // place would silently resolve to the wrong file.
+    let absolute = if raw.is_absolute() {
+        raw.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .map(|cwd| cwd.join(raw))
+            .unwrap_or_else(|_| raw.to_path_buf())
+    };
+    let absolute = lexically_normalize(&absolute);
+    let normalized = absolute.to_string_lossy().replace('/', "\\");
+
+    if normalized.starts_with(r"\\?\") {
+        return PathBuf::from(normalized);
+    }
+    if let Some(unc) = normalized.strip_prefix(r"\\") {
+        return PathBuf::from(format!(r"\\?\UNC\{}", unc));
+    }
+    PathBuf::from(format!(r"\\?\{}", normalized))
+}
+
+#[cfg(not(windows))]
+pub(crate) fn long_path(path: &str) -> PathBuf {
+    PathBuf::from(path)
+}
+
+/// Resolve `.`/`..` components without touching the filesystem.
+#[cfg(windows)]
+fn lexically_normalize(path: &Path) -> PathBuf {
+    use std::path::Component;
+
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match result.components().last() {
+                Some(Component::Normal(_)) => {
+                    result.pop();
+                }
+                // `..` directly above a root stays at that root, as Win32 resolves it.
+                Some(Component::RootDir) => {}
+                _ => result.push(component.as_os_str()),
+            },
+            other => result.push(other.as_os_str()),
+        }
+    }
+    result
+}
+
 #[derive(Error, Debug)]
 enum CommandError {
     #[error("File operation failed: {0}")]
@@ -50,8 +110,8 @@ struct RecentFile {
 /// Read MOL/SDF file content
 #[tauri::command]
 async fn read_mol_file(path: String) -> CommandResult<String> {
-    let file_path = Path::new(&path);
-    
+    let file_path = long_path(&path);
+
     if !file_path.exists() {
         return Err(CommandError::InvalidPath(format!("File not found: {}", path)));
     }
@@ -80,17 +140,17 @@ async fn write_mol_file(path: String, content: String) -> CommandResult<()> {
         return Err(CommandError::InvalidContent);
     }
     
-    let file_path = Path::new(&path);
-    
+    let file_path = long_path(&path);
+
     // Validate file extension
     let ext = file_path.extension()
         .and_then(|e| e.to_str())
         .unwrap_or("");
-    
+
     if !matches!(ext.to_lowercase().as_str(), "mol" | "sdf" | "sd") {
         return Err(CommandError::InvalidPath(format!("Invalid file type: {}", ext)));
     }
-    
+
     // Create parent directory if it doesn't exist
     if let Some(parent) = file_path.parent() {
         if !parent.exists() {
@@ -105,7 +165,7 @@ async fn write_mol_file(path: String, content: String) -> CommandResult<()> {
 /// Read text file (generic)
 #[tauri::command]
 async fn read_text_file(path: String) -> CommandResult<String> {
-    let file_path = Path::new(&path);
+    let file_path = long_path(&path);
     
     if !file_path.exists() {
         return Err(CommandError::InvalidPath(format!("File not found: {}", path)));
@@ -118,7 +178,7 @@ async fn read_text_file(path: String) -> CommandResult<String> {
 /// Write text file (generic)
 #[tauri::command]
 async fn write_text_file(path: String, content: String) -> CommandResult<()> {
-    let file_path = Path::new(&path);
+    let file_path = long_path(&path);
     
     // Create parent directory if it doesn't exist
     if let Some(parent) = file_path.parent() {
@@ -134,14 +194,14 @@ async fn write_text_file(path: String, content: String) -> CommandResult<()> {
 /// Check if file exists
 #[tauri::command]
 async fn file_exists(path: String) -> CommandResult<bool> {
-    let file_path = Path::new(&path);
+    let file_path = long_path(&path);
     Ok(file_path.exists() && file_path.is_file())
 }
 
 /// Get file metadata
 #[tauri::command]
 async fn get_file_info(path: String) -> CommandResult<serde_json::Value> {
-    let file_path = Path::new(&path);
+    let file_path = long_path(&path);
     
     if !file_path.exists() {
         return Err(CommandError::InvalidPath(format!("File not found: {}", path)));
@@ -173,7 +233,7 @@ async fn get_file_info(path: String) -> CommandResult<serde_json::Value> {
 /// Read directory contents (for file browser)
 #[tauri::command]
 async fn read_directory(path: String, filter_ext: Option<Vec<String>>) -> CommandResult<Vec<serde_json::Value>> {
-    let dir_path = Path::new(&path);
+    let dir_path = long_path(&path);
     
     if !dir_path.exists() {
         return Err(CommandError::InvalidPath(format!("Directory not found: {}", path)));
@@ -183,34 +243,39 @@ async fn read_directory(path: String, filter_ext: Option<Vec<String>>) -> Comman
         return Err(CommandError::InvalidPath(format!("Not a directory: {}", path)));
     }
     
-    let entries = fs::read_dir(dir_path)?;
+    let entries = fs::read_dir(&dir_path)?;
     let mut files = Vec::new();
-    
+    let display_dir = Path::new(&path);
+
     for entry in entries {
         let entry = entry?;
-        let path = entry.path();
+        let entry_path = entry.path();
         let metadata = entry.metadata()?;
-        
-        let file_name = path.file_name()
+
+        let file_name = entry_path.file_name()
             .and_then(|n| n.to_str())
             .unwrap_or("unknown")
             .to_string();
-        
-        let ext = path.extension()
+
+        let ext = entry_path.extension()
             .and_then(|e| e.to_str())
             .map(|s| s.to_lowercase())
             .unwrap_or_default();
-        
+
         // Apply filter if specified
         if let Some(ref filters) = filter_ext {
             if !filters.is_empty() && !filters.iter().any(|f| f.to_lowercase() == ext) {
                 continue;
             }
         }
-        
+
+        // Display path is built from the original (non-long-path) directory
+        // so the frontend never sees a `\\?\`-prefixed path.
+        let display_path = display_dir.join(&file_name);
+
         files.push(serde_json::json!({
             "name": file_name,
-            "path": path.to_string_lossy(),
+            "path": display_path.to_string_lossy(),
             "extension": ext,
             "is_file": metadata.is_file(),
             "is_dir": metadata.is_dir(),
@@ -237,16 +302,24 @@ async fn read_directory(path: String, filter_ext: Option<Vec<String>>) -> Comman
     Ok(files)
 }
 
-/// Copy PNG bytes to clipboard as EMF (Windows only, ChemDraw-style for FindMolecule)
+/// Copy PNG bytes to clipboard as EMF (Windows only, ChemDraw-style for FindMolecule).
+/// `mode` defaults to replacing the clipboard; pass `Append` to accumulate
+/// alongside other formats written in a separate call.
 #[tauri::command]
-async fn copy_png_as_emf(png_bytes: Vec<u8>) -> Result<(), String> {
-    clipboard_emf::write_png_as_emf_to_clipboard(&png_bytes)
+async fn copy_png_as_emf(
+    png_bytes: Vec<u8>,
+    mode: Option<clipboard_emf::ClipboardMode>,
+) -> Result<(), String> {
+    clipboard_emf::write_png_as_emf_to_clipboard(&png_bytes, mode.unwrap_or_default())
 }
 
 /// Copy CDX only to clipboard (Windows only)
 #[tauri::command]
-async fn copy_cdx_to_clipboard(cdx_bytes: Vec<u8>) -> Result<(), String> {
-    clipboard_emf::write_cdx_only_to_clipboard(&cdx_bytes)
+async fn copy_cdx_to_clipboard(
+    cdx_bytes: Vec<u8>,
+    mode: Option<clipboard_emf::ClipboardMode>,
+) -> Result<(), String> {
+    clipboard_emf::write_cdx_only_to_clipboard(&cdx_bytes, mode.unwrap_or_default())
 }
 
 /// Copy ChemDraw-style: EMF + MOL + CDX (Windows only)
@@ -255,14 +328,31 @@ async fn copy_chemdraw_style(
     png_bytes: Vec<u8>,
     mol_text: String,
     cdx_bytes: Option<Vec<u8>>,
+    mode: Option<clipboard_emf::ClipboardMode>,
 ) -> Result<(), String> {
     clipboard_emf::write_chemdraw_style_to_clipboard(
         &png_bytes,
         &mol_text,
         cdx_bytes.as_deref(),
+        mode.unwrap_or_default(),
     )
 }
 
+/// Export PNG bytes to a standalone .emf file on disk (Windows only), for
+/// a vector-format export that survives outside the live clipboard session.
+#[tauri::command]
+async fn write_emf_file(path: String, png_bytes: Vec<u8>) -> Result<(), String> {
+    clipboard_emf::write_emf_file(&path, &png_bytes)
+}
+
+/// Read CDX/MOL/EMF/DIB formats currently on the clipboard (Windows only),
+/// so the frontend can choose the richest representation ChemDraw or
+/// FindMolecule placed there.
+#[tauri::command]
+async fn read_from_clipboard() -> Result<clipboard_emf::ClipboardContents, String> {
+    clipboard_emf::read_from_clipboard()
+}
+
 /// Validate MOL file format
 #[tauri::command]
 async fn validate_mol_format(content: String) -> CommandResult<bool> {
@@ -295,7 +385,7 @@ async fn validate_mol_format(content: String) -> CommandResult<bool> {
 /// Read multiple files from a directory (for drag and drop support)
 #[tauri::command]
 async fn read_directory_files(path: String, extensions: Option<Vec<String>>) -> CommandResult<Vec<serde_json::Value>> {
-    let dir_path = Path::new(&path);
+    let dir_path = long_path(&path);
     
     if !dir_path.exists() {
         return Err(CommandError::InvalidPath(format!("Directory not found: {}", path)));
@@ -312,26 +402,36 @@ async fn read_directory_files(path: String, extensions: Option<Vec<String>>) ->
         "pdata".to_string(), "acqus".to_string()
     ]);
     
-    // Recursively read directory
-    fn read_dir_recursive(dir: &Path, allowed_exts: &[String], files: &mut Vec<serde_json::Value>) -> std::io::Result<()> {
+    // Recursively read directory. `dir` (long-path form) drives the actual
+    // fs calls so deep recursion keeps working past MAX_PATH; `display_dir`
+    // mirrors it without the `\\?\` prefix so the frontend only ever sees
+    // normal Windows paths.
+    fn read_dir_recursive(
+        dir: &Path,
+        display_dir: &Path,
+        allowed_exts: &[String],
+        files: &mut Vec<serde_json::Value>,
+    ) -> std::io::Result<()> {
         for entry in fs::read_dir(dir)? {
             let entry = entry?;
             let path = entry.path();
-            
+            let file_name = path.file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("unknown")
+                .to_string();
+            let display_path = display_dir.join(&file_name);
+
             if path.is_dir() {
                 // Recursively read subdirectories
-                read_dir_recursive(&path, allowed_exts, files)?;
+                read_dir_recursive(&path, &display_path, allowed_exts, files)?;
             } else if path.is_file() {
                 // Check if file has allowed extension
                 if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
                     if allowed_exts.iter().any(|allowed| allowed.eq_ignore_ascii_case(ext)) {
                         let metadata = fs::metadata(&path)?;
-                        let file_name = path.file_name()
-                            .and_then(|n| n.to_str())
-                            .unwrap_or("unknown");
-                        
+
                         files.push(serde_json::json!({
-                            "path": path.to_string_lossy(),
+                            "path": display_path.to_string_lossy(),
                             "name": file_name,
                             "extension": ext,
                             "size": metadata.len(),
@@ -348,7 +448,7 @@ async fn read_directory_files(path: String, extensions: Option<Vec<String>>) ->
         Ok(())
     }
     
-    read_dir_recursive(dir_path, &allowed_extensions, &mut files)?;
+    read_dir_recursive(&dir_path, Path::new(&path), &allowed_extensions, &mut files)?;
     Ok(files)
 }
 
@@ -363,6 +463,8 @@ pub fn run() {
             copy_png_as_emf,
             copy_cdx_to_clipboard,
             copy_chemdraw_style,
+            write_emf_file,
+            read_from_clipboard,
             read_mol_file,
             write_mol_file,
             read_text_file,